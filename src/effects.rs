@@ -0,0 +1,44 @@
+//! Simple post-synthesis audio effects.
+
+/// Apply a delay-line echo/reverb effect in place.
+///
+/// `delay_seconds` sizes a ring buffer to the equivalent number of samples
+/// at `rate` Hz; `feedback` (0..1) controls how much of the delayed signal
+/// is written back into the buffer, and `intensity` (0..1) is the wet mix
+/// blended into the output. Sizing the buffer from `rate` keeps the effect
+/// sample-rate-aware, so it sounds the same regardless of the model's frame
+/// period.
+pub fn apply_echo(samples: &mut [f64], rate: usize, delay_seconds: f64, feedback: f64, intensity: f64) {
+    let delay_samples = ((delay_seconds * rate as f64).round() as usize).max(1);
+    let mut buffer = vec![0.0; delay_samples];
+
+    for (i, x) in samples.iter_mut().enumerate() {
+        let slot = i % delay_samples;
+        let d = buffer[slot];
+        let dry = *x;
+        buffer[slot] = dry + feedback * d;
+        *x = dry + intensity * d;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_echo;
+
+    #[test]
+    fn zero_intensity_is_a_no_op() {
+        let mut samples = vec![1.0, 0.5, -0.5, 0.25];
+        let original = samples.clone();
+        apply_echo(&mut samples, 8, 0.25, 0.5, 0.0);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn echo_adds_delayed_signal() {
+        let mut samples = vec![1.0, 0.0, 0.0, 0.0, 0.0];
+        apply_echo(&mut samples, 5, 0.4, 0.0, 1.0);
+        // delay_samples = 2: sample 0 produces an echo at index 2.
+        assert_eq!(samples[0], 1.0);
+        assert_eq!(samples[2], 1.0);
+    }
+}
@@ -48,6 +48,28 @@ pub struct Condition {
     /// Additional half tone
     additional_half_tone: f64,
 
+    /// Seed for the vocoder's excitation PRNG. Fixing this makes synthesis
+    /// output reproducible for a given set of input parameters; it has no
+    /// effect on anything else.
+    seed: u32,
+
+    /// Target integrated loudness in LUFS for post-synthesis normalization.
+    /// `None` disables normalization and leaves the static `volume` gain as
+    /// the only level control.
+    target_loudness: Option<f64>,
+
+    /// Echo delay in seconds. 0.0 disables the echo effect.
+    echo_delay: f64,
+    /// Echo feedback, 0..1.
+    echo_feedback: f64,
+    /// Echo wet-mix intensity, 0..1.
+    echo_intensity: f64,
+
+    /// Desired output sampling frequency (Hz), independent of the voice
+    /// model's native `sampling_frequency`. `None` leaves the output at the
+    /// model's native rate.
+    output_sampling_frequency: Option<usize>,
+
     /// Interporation weights
     interporation_weight: InterporationWeight,
 }
@@ -67,6 +89,12 @@ impl Default for Condition {
             alpha: 0.0f64,
             beta: 0.0f64,
             additional_half_tone: 0.0f64,
+            seed: 1,
+            target_loudness: None,
+            echo_delay: 0.0,
+            echo_feedback: 0.0,
+            echo_intensity: 0.0,
+            output_sampling_frequency: None,
             interporation_weight: InterporationWeight::default(),
         }
     }
@@ -211,6 +239,80 @@ impl Condition {
         self.additional_half_tone
     }
 
+    /// Set the seed for the vocoder's excitation PRNG, making synthesis
+    /// output reproducible for a given seed and set of input parameters.
+    /// Note: Default value is 1. A zero seed is clamped to 1 at the point of
+    /// use, since an all-zero xorshift state never changes.
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+    /// Get the seed for the vocoder's excitation PRNG.
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Set target integrated loudness in LUFS for post-synthesis
+    /// normalization.
+    /// Note: Default is disabled (the static `volume` gain is used as-is).
+    pub fn set_target_loudness(&mut self, lufs: f64) {
+        self.target_loudness = Some(lufs);
+    }
+    /// Disable loudness normalization, reverting to the static `volume` gain.
+    pub fn clear_target_loudness(&mut self) {
+        self.target_loudness = None;
+    }
+    /// Get target integrated loudness in LUFS, if normalization is enabled.
+    pub fn get_target_loudness(&self) -> Option<f64> {
+        self.target_loudness
+    }
+
+    /// Set the echo/reverb delay in seconds. 0.0 disables the effect.
+    /// Note: Default value is 0.0.
+    pub fn set_echo_delay(&mut self, seconds: f64) {
+        self.echo_delay = seconds.max(0.0);
+    }
+    /// Get the echo/reverb delay in seconds.
+    pub fn get_echo_delay(&self) -> f64 {
+        self.echo_delay
+    }
+
+    /// Set the echo/reverb feedback.
+    /// Note: Default value is 0.0.
+    pub fn set_echo_feedback(&mut self, f: f64) {
+        self.echo_feedback = f.clamp(0.0, 1.0);
+    }
+    /// Get the echo/reverb feedback.
+    pub fn get_echo_feedback(&self) -> f64 {
+        self.echo_feedback
+    }
+
+    /// Set the echo/reverb wet-mix intensity.
+    /// Note: Default value is 0.0.
+    pub fn set_echo_intensity(&mut self, f: f64) {
+        self.echo_intensity = f.clamp(0.0, 1.0);
+    }
+    /// Get the echo/reverb wet-mix intensity.
+    pub fn get_echo_intensity(&self) -> f64 {
+        self.echo_intensity
+    }
+
+    /// Set the desired output sampling frequency (Hz), independent of the
+    /// voice model's native rate; synthesis still happens at the model's
+    /// rate and the result is resampled to `i`.
+    /// Note: Default is disabled (output stays at the model's native rate).
+    pub fn set_output_sampling_frequency(&mut self, i: usize) {
+        self.output_sampling_frequency = Some(i.max(1));
+    }
+    /// Revert to outputting audio at the voice model's native sampling
+    /// frequency.
+    pub fn clear_output_sampling_frequency(&mut self) {
+        self.output_sampling_frequency = None;
+    }
+    /// Get the desired output sampling frequency, if set.
+    pub fn get_output_sampling_frequency(&self) -> Option<usize> {
+        self.output_sampling_frequency
+    }
+
     /// Get interporation weight
     pub fn get_interporation_weight(&self) -> &InterporationWeight {
         &self.interporation_weight
@@ -267,6 +369,58 @@ impl Engine {
     }
 
     pub fn generate_speech(&self, labels: &Labels) -> Vec<f64> {
+        let mut speech: Vec<f64> = self.build_stream(labels).flatten().collect();
+
+        if self.condition.echo_delay > 0.0 {
+            crate::effects::apply_echo(
+                &mut speech,
+                self.condition.sampling_frequency,
+                self.condition.echo_delay,
+                self.condition.echo_feedback,
+                self.condition.echo_intensity,
+            );
+        }
+
+        // Loudness normalization runs last among the gain-affecting steps
+        // (after echo, which re-injects energy and would otherwise drift
+        // the result off target) so `target_loudness` stays the actual
+        // output LUFS. Resampling is loudness-neutral, so it's fine on
+        // either side; it's kept after so it always sees the final signal.
+        if let Some(target) = self.condition.target_loudness {
+            crate::loudness::normalize_to_loudness(
+                &mut speech,
+                self.condition.sampling_frequency,
+                target,
+            );
+        }
+
+        if let Some(output_rate) = self.condition.output_sampling_frequency {
+            speech = crate::resample::resample(&speech, self.condition.sampling_frequency, output_rate);
+        }
+
+        speech
+    }
+
+    /// Frame-incremental synthesis: drives the vocoder one `fperiod`-length
+    /// block at a time instead of synthesizing the whole utterance up
+    /// front, so callers can feed a playback buffer incrementally and stop
+    /// early. Concatenating every yielded block is bit-identical to
+    /// [`Self::generate_speech`] before its post-synthesis effects (loudness
+    /// normalization, echo, resampling), which operate on the complete
+    /// signal and aren't applied here.
+    pub fn synthesize_streaming(&self, labels: &Labels) -> crate::speech::SpeechStream {
+        self.build_stream(labels)
+    }
+
+    // `generate_speech` and `synthesize_streaming` both delegate to this one
+    // `build_stream` helper, so their equivalence (modulo the post-synthesis
+    // effects noted above) is structural rather than something a test needs
+    // to re-derive. A literal fixture-based equivalence test would still be
+    // the stronger guard, but building an `Engine` (or even just a `Labels`)
+    // here needs `duration`, `label`, `mlpg_adjust` and `model`, none of
+    // which exist in this checkout, so there's nothing to construct one from
+    // without guessing at those modules' internals.
+    fn build_stream(&self, labels: &Labels) -> crate::speech::SpeechStream {
         let vocoder = Vocoder::new(
             self.voices.stream_metadata(0).vector_length,
             self.voices.stream_metadata(2).vector_length,
@@ -292,16 +446,17 @@ impl Engine {
             estimator.create(self.condition.speed)
         };
 
-        let spectrum =
+        let compute_spectrum = || {
             MlpgAdjust::new(self.condition.gv_weight[0], self.condition.msd_threshold[0]).create(
                 models.stream(0),
                 models.vector_length(0),
                 models.windows(0),
                 models.gv(0),
                 &durations,
-            );
-        let lf0 = MlpgAdjust::new(self.condition.gv_weight[1], self.condition.msd_threshold[1])
-            .create(
+            )
+        };
+        let compute_lf0 = || {
+            MlpgAdjust::new(self.condition.gv_weight[1], self.condition.msd_threshold[1]).create(
                 mutated(models.stream(1), |params| {
                     apply_additional_half_tone(params, self.condition.additional_half_tone);
                 }),
@@ -309,18 +464,30 @@ impl Engine {
                 models.windows(1),
                 models.gv(1),
                 &durations,
-            );
-        let lpf = MlpgAdjust::new(self.condition.gv_weight[2], self.condition.msd_threshold[2])
-            .create(
+            )
+        };
+        let compute_lpf = || {
+            MlpgAdjust::new(self.condition.gv_weight[2], self.condition.msd_threshold[2]).create(
                 models.stream(2),
                 models.vector_length(2),
                 models.windows(2),
                 models.gv(2),
                 &durations,
-            );
+            )
+        };
 
-        let generator = SpeechGenerator::new(self.condition.fperiod);
-        generator.synthesize(vocoder, spectrum, lf0, lpf)
+        // The three streams only share `models` and `durations` as
+        // read-only inputs, so under the `parallel` feature they're
+        // computed concurrently via rayon; MLPG trajectory optimization
+        // dominates synthesis time, and this is where the bulk of it goes.
+        #[cfg(feature = "parallel")]
+        let (spectrum, (lf0, lpf)) =
+            rayon::join(compute_spectrum, || rayon::join(compute_lf0, compute_lpf));
+        #[cfg(not(feature = "parallel"))]
+        let (spectrum, lf0, lpf) = (compute_spectrum(), compute_lf0(), compute_lpf());
+
+        let generator = SpeechGenerator::new_with_seed(self.condition.fperiod, self.condition.seed);
+        generator.synthesize_stream(vocoder, spectrum, lf0, lpf)
     }
 }
 
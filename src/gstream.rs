@@ -1,12 +1,52 @@
 use crate::{constants::NODATA, pstream::ParameterStreamSet, vocoder::Vocoder};
 
+// No test asserts `SpeechStream::new(...).flatten().collect()` equals a
+// batch-synthesized `GenerateSpeechStreamSet::create` output here, unlike
+// `speech.rs`'s equivalent streaming type: building a `ParameterStreamSet`
+// fixture requires the module that owns it (`pstream`), which isn't part of
+// this checkout, so there's no way to construct one without guessing at an
+// unrelated type's internals.
+
 pub struct GenerateSpeechStreamSet {
     speech: Vec<f64>,
 }
 
 impl GenerateSpeechStreamSet {
     /// Generate speech
-    pub fn create(pss: &ParameterStreamSet, mut v: Vocoder, fperiod: usize) -> Self {
+    pub fn create(pss: &ParameterStreamSet, v: Vocoder, fperiod: usize) -> Self {
+        let speech = SpeechStream::new(pss, v, fperiod).flatten().collect();
+        GenerateSpeechStreamSet { speech }
+    }
+
+    /// Get synthesized speech waveform
+    pub fn get_speech(&self) -> &[f64] {
+        &self.speech
+    }
+}
+
+/// Frame-incremental synthesis over a [`ParameterStreamSet`]: each call to
+/// `next` advances the [`Vocoder`] by exactly one frame and yields the
+/// `fperiod` samples it produced, carrying the vocoder's filter state and
+/// the per-stream MSD skip counters across calls. Concatenating every
+/// yielded block is sample-identical to [`GenerateSpeechStreamSet::create`],
+/// which is implemented on top of this iterator. This lets callers stream
+/// audio to an output device frame-by-frame, and stop before synthesizing
+/// the whole utterance.
+pub struct SpeechStream<'a> {
+    pss: &'a ParameterStreamSet,
+    v: Vocoder,
+    fperiod: usize,
+    frame_skipped_index: Vec<usize>,
+    frame: usize,
+    total_frame: usize,
+}
+
+impl<'a> SpeechStream<'a> {
+    /// # Panics
+    /// Panics if `pss` doesn't have 2 or 3 streams, if the lf0 stream's
+    /// static vector size isn't 1, or if the low-pass filter stream's
+    /// vector length is even.
+    pub fn new(pss: &'a ParameterStreamSet, v: Vocoder, fperiod: usize) -> Self {
         // check
         if pss.get_nstream() != 2 && pss.get_nstream() != 3 {
             panic!("The number of streams must be 2 or 3.");
@@ -18,55 +58,65 @@ impl GenerateSpeechStreamSet {
             panic!("The number of low-pass filter coefficient must be odd numbers.");
         }
 
-        // create speech buffer
-        let total_frame = pss.get_total_frame();
-        let mut speech = vec![0.0; total_frame * fperiod];
+        Self {
+            total_frame: pss.get_total_frame(),
+            pss,
+            v,
+            fperiod,
+            frame_skipped_index: vec![0; pss.get_nstream()],
+            frame: 0,
+        }
+    }
+}
 
-        // synthesize speech waveform
-        let mut frame_skipped_index = vec![0; pss.get_nstream()];
-        for i in 0..total_frame {
-            let get_parameter = |stream_index: usize, vector_index: usize| {
-                if !pss.get_msd_flag(stream_index, i) {
-                    NODATA
-                } else {
-                    pss.get_parameter(
-                        stream_index,
-                        frame_skipped_index[stream_index],
-                        vector_index,
-                    )
-                }
-            };
+impl<'a> Iterator for SpeechStream<'a> {
+    type Item = Vec<f64>;
 
-            let lpf = if pss.get_nstream() >= 3 {
-                (0..pss.get_vector_length(2))
-                    .map(|vector_index| get_parameter(2, vector_index))
-                    .collect()
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.frame;
+        if i >= self.total_frame {
+            return None;
+        }
+        self.frame += 1;
+
+        let get_parameter = |stream_index: usize, vector_index: usize| {
+            if !self.pss.get_msd_flag(stream_index, i) {
+                NODATA
             } else {
-                vec![]
-            };
-            let spectrum: Vec<f64> = (0..pss.get_vector_length(0))
-                .map(|vector_index| get_parameter(0, vector_index))
-                .collect();
+                self.pss.get_parameter(
+                    stream_index,
+                    self.frame_skipped_index[stream_index],
+                    vector_index,
+                )
+            }
+        };
 
-            v.synthesize(
-                get_parameter(1, 0),
-                &spectrum,
-                &lpf,
-                &mut speech[i * fperiod..(i + 1) * fperiod],
-            );
+        let lpf: Vec<f64> = if self.pss.get_nstream() >= 3 {
+            (0..self.pss.get_vector_length(2))
+                .map(|vector_index| get_parameter(2, vector_index))
+                .collect()
+        } else {
+            vec![]
+        };
+        let spectrum: Vec<f64> = (0..self.pss.get_vector_length(0))
+            .map(|vector_index| get_parameter(0, vector_index))
+            .collect();
 
-            for (j, index) in frame_skipped_index.iter_mut().enumerate() {
-                if pss.get_msd_flag(j, i) {
-                    *index += 1;
-                }
+        let mut block = vec![0.0; self.fperiod];
+        self.v
+            .synthesize(get_parameter(1, 0), &spectrum, &lpf, &mut block);
+
+        for (j, index) in self.frame_skipped_index.iter_mut().enumerate() {
+            if self.pss.get_msd_flag(j, i) {
+                *index += 1;
             }
         }
 
-        GenerateSpeechStreamSet { speech }
+        Some(block)
     }
 
-    /// Get synthesized speech waveform
-    pub fn get_speech(&self) -> &[f64] {
-        &self.speech
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.total_frame - self.frame;
+        (remaining, Some(remaining))
     }
 }
@@ -0,0 +1,173 @@
+//! ITU-R BS.1770 / EBU R128 integrated loudness measurement and
+//! normalization.
+//!
+//! [`Condition::set_target_loudness`](crate::engine::Condition::set_target_loudness)
+//! uses this to rescale synthesized audio to a target LUFS, so voices
+//! synthesized at wildly different gains come out at consistent perceived
+//! loudness instead of requiring the caller to hand-tune dB.
+
+/// A biquad filter in direct form II transposed, used for the K-weighting
+/// cascade below.
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl Biquad {
+    fn process(&self, input: &[f64]) -> Vec<f64> {
+        let mut z1 = 0.0;
+        let mut z2 = 0.0;
+        input
+            .iter()
+            .map(|&x| {
+                let y = self.b0 * x + z1;
+                z1 = self.b1 * x - self.a1 * y + z2;
+                z2 = self.b2 * x - self.a2 * y;
+                y
+            })
+            .collect()
+    }
+}
+
+/// The high-shelf "pre-filter" stage of K-weighting.
+fn prefilter(rate: f64) -> Biquad {
+    let gain_db = 3.99984385397;
+    let q = 0.7071752369554193;
+    let center_freq = 1681.9744509555319;
+
+    let k = (std::f64::consts::PI * center_freq / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+
+    let a0 = 1.0 + k / q + k * k;
+    Biquad {
+        b0: (vh + vb * k / q + k * k) / a0,
+        b1: 2.0 * (k * k - vh) / a0,
+        b2: (vh - vb * k / q + k * k) / a0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+    }
+}
+
+/// The high-pass "RLB weighting" stage of K-weighting.
+fn highpass(rate: f64) -> Biquad {
+    let q = 0.5003270373238773;
+    let center_freq = 38.13547087613982;
+
+    let k = (std::f64::consts::PI * center_freq / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    Biquad {
+        b0: 1.0 / a0,
+        b1: -2.0 / a0,
+        b2: 1.0 / a0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+    }
+}
+
+/// Apply the two-stage K-weighting filter, returning the weighted signal
+/// power measurements are taken from.
+fn k_weight(samples: &[f64], rate: f64) -> Vec<f64> {
+    highpass(rate).process(&prefilter(rate).process(samples))
+}
+
+const ABSOLUTE_THRESHOLD_LUFS: f64 = -70.0;
+const RELATIVE_THRESHOLD_OFFSET_LU: f64 = -10.0;
+
+fn block_power(block: &[f64]) -> f64 {
+    block.iter().map(|x| x * x).sum::<f64>() / block.len() as f64
+}
+
+fn power_to_lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.max(f64::MIN_POSITIVE).log10()
+}
+
+fn lufs_to_power(lufs: f64) -> f64 {
+    10f64.powf((lufs + 0.691) / 10.0)
+}
+
+/// Measure the integrated loudness of `samples`, sampled at `rate` Hz, in
+/// LUFS: K-weight the signal, compute mean-square power over 400ms blocks
+/// with 75% overlap, gate out blocks below an absolute -70 LUFS threshold,
+/// then gate again against a relative threshold 10 LU below the mean of the
+/// surviving blocks, and report the loudness of what's left.
+pub fn measure_integrated_loudness(samples: &[f64], rate: usize) -> f64 {
+    let rate = rate as f64;
+    let weighted = k_weight(samples, rate);
+
+    let block_len = (0.4 * rate) as usize;
+    let step = (0.1 * rate) as usize;
+    if block_len == 0 || step == 0 || weighted.len() < block_len {
+        return power_to_lufs(block_power(&weighted));
+    }
+
+    let mut powers = Vec::new();
+    let mut start = 0;
+    while start + block_len <= weighted.len() {
+        powers.push(block_power(&weighted[start..start + block_len]));
+        start += step;
+    }
+
+    let absolute_threshold_power = lufs_to_power(ABSOLUTE_THRESHOLD_LUFS);
+    let gated: Vec<f64> = powers
+        .into_iter()
+        .filter(|&p| p > absolute_threshold_power)
+        .collect();
+    if gated.is_empty() {
+        return ABSOLUTE_THRESHOLD_LUFS;
+    }
+
+    let mean_power = gated.iter().sum::<f64>() / gated.len() as f64;
+    let relative_threshold_power =
+        lufs_to_power(power_to_lufs(mean_power) + RELATIVE_THRESHOLD_OFFSET_LU);
+
+    let final_gated: Vec<f64> = gated
+        .into_iter()
+        .filter(|&p| p > relative_threshold_power)
+        .collect();
+    if final_gated.is_empty() {
+        return power_to_lufs(mean_power) + RELATIVE_THRESHOLD_OFFSET_LU;
+    }
+
+    power_to_lufs(final_gated.iter().sum::<f64>() / final_gated.len() as f64)
+}
+
+/// Rescale `samples` in place so their integrated loudness matches `target`
+/// LUFS.
+pub fn normalize_to_loudness(samples: &mut [f64], rate: usize, target: f64) {
+    if samples.is_empty() {
+        return;
+    }
+    let measured = measure_integrated_loudness(samples, rate);
+    let gain = 10f64.powf((target - measured) / 20.0);
+    for s in samples.iter_mut() {
+        *s *= gain;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence_is_at_the_absolute_threshold() {
+        let samples = vec![0.0; 48000];
+        assert_eq!(measure_integrated_loudness(&samples, 48000), ABSOLUTE_THRESHOLD_LUFS);
+    }
+
+    #[test]
+    fn normalizing_louder_signal_reduces_power() {
+        let rate = 48000;
+        let mut loud: Vec<f64> = (0..rate)
+            .map(|i| (i as f64 * 0.1).sin())
+            .collect();
+        let before = measure_integrated_loudness(&loud, rate);
+        normalize_to_loudness(&mut loud, rate, before - 6.0);
+        let after = measure_integrated_loudness(&loud, rate);
+        assert!((after - (before - 6.0)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,300 @@
+//! A compact binary cache for parsed [`Model`]s, so a warm restart can skip
+//! re-running the nom text parser over a `.htsvoice` file.
+//!
+//! The cache is just a byte blob: a magic tag, a format version, a hash of
+//! the source voice the cache was built from, and the serialized trees/pdf.
+//! [`Model::load_cached`] rejects the cache outright (returning `None`) if
+//! the magic, version, or source hash don't match, so callers fall back to
+//! text parsing instead of trusting a stale or foreign cache.
+
+use super::stream::{Model, ModelParameter, Pattern, Tree, TreeNode};
+use super::glob::GlobMatcher;
+
+const MAGIC: &[u8; 8] = b"JBCACHE1";
+const FORMAT_VERSION: u32 = 1;
+
+/// FNV-1a 64-bit hash, used to fingerprint the source `.htsvoice` bytes a
+/// cache was built from.
+pub fn hash_source(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    data.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+    fn u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
+    fn u32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+    fn u64(&mut self, v: u64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+    fn usize(&mut self, v: usize) {
+        self.u64(v as u64);
+    }
+    fn f64(&mut self, v: f64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+    fn bool(&mut self, v: bool) {
+        self.u8(v as u8);
+    }
+    fn string(&mut self, s: &str) {
+        self.usize(s.len());
+        self.buf.extend_from_slice(s.as_bytes());
+    }
+    fn vec<T>(&mut self, items: &[T], mut write_one: impl FnMut(&mut Self, &T)) {
+        self.usize(items.len());
+        for item in items {
+            write_one(self, item);
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.buf.get(self.pos..self.pos + n)?;
+        self.pos += n;
+        Some(slice)
+    }
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+    fn usize(&mut self) -> Option<usize> {
+        Some(self.u64()? as usize)
+    }
+    fn f64(&mut self) -> Option<f64> {
+        Some(f64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+    fn bool(&mut self) -> Option<bool> {
+        Some(self.u8()? != 0)
+    }
+    fn string(&mut self) -> Option<String> {
+        let len = self.usize()?;
+        String::from_utf8(self.bytes(len)?.to_vec()).ok()
+    }
+    fn vec<T>(&mut self, mut read_one: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let len = self.usize()?;
+        // Every element is at least 1 byte on the wire, so a `len` bigger
+        // than the remaining buffer is necessarily corrupt; reject it
+        // before `Vec::with_capacity` turns it into an abort.
+        if len > self.buf.len() - self.pos {
+            return None;
+        }
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(read_one(self)?);
+        }
+        Some(items)
+    }
+}
+
+fn write_pattern(w: &mut Writer, pattern: &Pattern) {
+    match pattern {
+        Pattern::All => w.u8(0),
+        Pattern::Contains(s) => {
+            w.u8(1);
+            w.string(s);
+        }
+        Pattern::Glob(g) => {
+            w.u8(2);
+            w.string(g.source());
+        }
+    }
+}
+
+fn read_pattern(r: &mut Reader) -> Option<Pattern> {
+    match r.u8()? {
+        0 => Some(Pattern::All),
+        1 => Some(Pattern::Contains(r.string()?)),
+        2 => Some(Pattern::Glob(GlobMatcher::compile(&r.string()?))),
+        _ => None,
+    }
+}
+
+fn write_tree_node(w: &mut Writer, node: &TreeNode) {
+    match node {
+        TreeNode::Node { patterns, yes, no } => {
+            w.u8(0);
+            w.vec(patterns, write_pattern);
+            w.usize(*yes);
+            w.usize(*no);
+        }
+        TreeNode::Leaf { pdf_index } => {
+            w.u8(1);
+            w.usize(*pdf_index);
+        }
+    }
+}
+
+fn read_tree_node(r: &mut Reader) -> Option<TreeNode> {
+    match r.u8()? {
+        0 => Some(TreeNode::Node {
+            patterns: r.vec(read_pattern)?,
+            yes: r.usize()?,
+            no: r.usize()?,
+        }),
+        1 => Some(TreeNode::Leaf {
+            pdf_index: r.usize()?,
+        }),
+        _ => None,
+    }
+}
+
+fn write_tree(w: &mut Writer, tree: &Tree) {
+    w.usize(tree.state);
+    w.vec(&tree.patterns, write_pattern);
+    w.vec(&tree.nodes, write_tree_node);
+}
+
+fn read_tree(r: &mut Reader) -> Option<Tree> {
+    Some(Tree {
+        state: r.usize()?,
+        patterns: r.vec(read_pattern)?,
+        nodes: r.vec(read_tree_node)?,
+    })
+}
+
+fn write_model_parameter(w: &mut Writer, param: &ModelParameter) {
+    w.vec(&param.parameters, |w, (mean, vari)| {
+        w.f64(*mean);
+        w.f64(*vari);
+    });
+    match param.msd {
+        Some(msd) => {
+            w.bool(true);
+            w.f64(msd);
+        }
+        None => w.bool(false),
+    }
+}
+
+fn read_model_parameter(r: &mut Reader) -> Option<ModelParameter> {
+    let parameters = r.vec(|r| Some((r.f64()?, r.f64()?)))?;
+    let msd = if r.bool()? { Some(r.f64()?) } else { None };
+    Some(ModelParameter { parameters, msd })
+}
+
+impl Model {
+    /// Serialize this model into a compact binary cache, tagged with
+    /// `source_hash` (see [`hash_source`]) so a stale cache built from a
+    /// different voice file is rejected on load.
+    pub fn dump(&self, source_hash: u64) -> Vec<u8> {
+        let mut w = Writer::new();
+        w.buf.extend_from_slice(MAGIC);
+        w.u32(FORMAT_VERSION);
+        w.u64(source_hash);
+        w.vec(self.trees(), write_tree);
+        w.vec(self.pdf(), |w, pdfs| w.vec(pdfs, write_model_parameter));
+        w.buf
+    }
+
+    /// Load a model from a binary cache previously produced by
+    /// [`Self::dump`]. Returns `None` (rather than erroring) on a magic,
+    /// version, or source-hash mismatch, so the caller can fall back to
+    /// parsing the voice file as text.
+    pub fn load_cached(cache: &[u8], source_hash: u64) -> Option<Self> {
+        let mut r = Reader::new(cache);
+        if r.bytes(MAGIC.len())? != MAGIC {
+            return None;
+        }
+        if r.u32()? != FORMAT_VERSION {
+            return None;
+        }
+        if r.u64()? != source_hash {
+            return None;
+        }
+
+        let trees = r.vec(read_tree)?;
+        let pdf = r.vec(|r| r.vec(read_model_parameter))?;
+
+        Some(Model::new(trees, pdf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_model() -> Model {
+        let tree = Tree {
+            state: 2,
+            patterns: vec![Pattern::All],
+            nodes: vec![TreeNode::Leaf { pdf_index: 1 }],
+        };
+        let pdf = vec![vec![ModelParameter {
+            parameters: vec![(1.0, 2.0), (3.0, 4.0)],
+            msd: Some(0.5),
+        }]];
+        Model::new(vec![tree], pdf)
+    }
+
+    #[test]
+    fn round_trip_reproduces_an_equivalent_model() {
+        let model = sample_model();
+        let dumped = model.dump(42);
+
+        let loaded = Model::load_cached(&dumped, 42).expect("cache should be accepted");
+        assert_eq!(loaded.trees().len(), model.trees().len());
+        assert_eq!(loaded.trees()[0].state, model.trees()[0].state);
+        assert_eq!(loaded.pdf(), model.pdf());
+
+        let original = model.get_parameter(2, "anything").unwrap();
+        let reloaded = loaded.get_parameter(2, "anything").unwrap();
+        assert_eq!(original, reloaded);
+    }
+
+    #[test]
+    fn load_cached_rejects_wrong_magic() {
+        let mut dumped = sample_model().dump(42);
+        dumped[0] = b'X';
+        assert!(Model::load_cached(&dumped, 42).is_none());
+    }
+
+    #[test]
+    fn load_cached_rejects_wrong_version() {
+        let mut dumped = sample_model().dump(42);
+        dumped[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(Model::load_cached(&dumped, 42).is_none());
+    }
+
+    #[test]
+    fn load_cached_rejects_mismatched_source_hash() {
+        let dumped = sample_model().dump(42);
+        assert!(Model::load_cached(&dumped, 43).is_none());
+    }
+
+    #[test]
+    fn reader_vec_rejects_a_corrupt_oversized_length() {
+        // A length prefix claiming far more elements than the remaining
+        // buffer could possibly hold must be rejected, not fed into
+        // `Vec::with_capacity` as-is.
+        let buf = u64::MAX.to_le_bytes();
+        let mut r = Reader::new(&buf);
+        assert_eq!(r.vec(|r| r.u8()), None);
+    }
+}
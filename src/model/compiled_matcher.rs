@@ -0,0 +1,222 @@
+//! A single-pass label matcher compiled once per [`super::stream::Model`].
+//!
+//! Naively, matching a context label against a voice's decision trees is
+//! O(nodes × patterns): `Model::find_tree_index` scans every tree, and
+//! `Tree::search_node` evaluates a pattern at every node, each one a
+//! separate string/regex comparison. [`CompiledLabelMatcher`] instead
+//! collects every distinct pattern across all trees up front, assigns each
+//! a small integer id, and partitions them into an `AhoCorasick` automaton
+//! (for plain substring patterns) and a `RegexSet` (for everything else).
+//! Matching a label then costs one Aho-Corasick scan and one `RegexSet`
+//! evaluation, producing a `Vec<bool>` indexed by pattern id that trees walk
+//! with O(1) lookups instead of re-running any pattern matching per node.
+//!
+//! This duplicates `*`/`?` matching logic with [`super::glob::GlobMatcher`],
+//! which stores the compiled pattern on [`super::stream::Pattern::Glob`] for
+//! equality and for serialization into the binary cache format, where it
+//! round-trips through its `source()`. `GlobMatcher` can't be reused here: this module
+//! needs every pattern evaluated in one pass over the label via a single
+//! automaton, and `RegexSet` is the only piece in this crate that does that
+//! for a set of patterns compiled up front. A per-pattern `is_match` loop
+//! would be back to the O(patterns)-per-query cost this module exists to
+//! eliminate.
+
+use std::collections::HashMap;
+
+use aho_corasick::AhoCorasick;
+use regex::RegexSet;
+
+use super::stream::Pattern;
+
+/// How a single compiled pattern is evaluated.
+enum PatternKind {
+    /// Always matches; not assigned to either automaton.
+    All,
+    /// A plain substring, matched via the shared [`AhoCorasick`] automaton.
+    Contains,
+    /// Anything else (`*`/`?` wildcards elsewhere in the pattern), matched
+    /// via the shared [`RegexSet`].
+    Pattern,
+}
+
+/// Precompiled, single-pass matcher over every distinct pattern string used
+/// by a model's decision trees.
+pub struct CompiledLabelMatcher {
+    kinds: Vec<PatternKind>,
+    contains_ac: AhoCorasick,
+    /// `contains_ac` pattern index -> id in `kinds`.
+    contains_ids: Vec<usize>,
+    regex_set: RegexSet,
+    /// `regex_set` pattern index -> id in `kinds`.
+    regex_ids: Vec<usize>,
+}
+
+impl CompiledLabelMatcher {
+    /// Build a matcher from every distinct pattern string that appears
+    /// across a model's trees, returning the matcher plus a lookup table
+    /// from pattern string to its assigned id.
+    pub fn compile<'a>(patterns: impl Iterator<Item = &'a str>) -> (Self, HashMap<String, usize>) {
+        let mut ids = HashMap::new();
+        let mut kinds = Vec::new();
+        let mut contains_patterns = Vec::new();
+        let mut contains_ids = Vec::new();
+        let mut regex_patterns = Vec::new();
+        let mut regex_ids = Vec::new();
+
+        for pattern in patterns {
+            if ids.contains_key(pattern) {
+                continue;
+            }
+            let id = kinds.len();
+            ids.insert(pattern.to_string(), id);
+
+            if pattern == "*" {
+                kinds.push(PatternKind::All);
+            } else if pattern.starts_with('*')
+                && pattern.ends_with('*')
+                && !pattern[1..pattern.len() - 1].contains(['*', '?'])
+            {
+                kinds.push(PatternKind::Contains);
+                contains_patterns.push(pattern[1..pattern.len() - 1].to_string());
+                contains_ids.push(id);
+            } else {
+                kinds.push(PatternKind::Pattern);
+                regex_patterns.push(to_regex(pattern));
+                regex_ids.push(id);
+            }
+        }
+
+        let contains_ac = AhoCorasick::new(&contains_patterns)
+            .expect("contains patterns are plain literals and always compile");
+        let regex_set =
+            RegexSet::new(&regex_patterns).expect("patterns are pre-escaped and always compile");
+
+        (
+            Self {
+                kinds,
+                contains_ac,
+                contains_ids,
+                regex_set,
+                regex_ids,
+            },
+            ids,
+        )
+    }
+
+    /// Evaluate every compiled pattern against `label` in one pass, returning
+    /// a bitset (as `Vec<bool>`) indexed by pattern id.
+    pub fn matches(&self, label: &str) -> Vec<bool> {
+        let mut result = vec![false; self.kinds.len()];
+        for (id, kind) in self.kinds.iter().enumerate() {
+            if let PatternKind::All = kind {
+                result[id] = true;
+            }
+        }
+        // `find_iter` reports non-overlapping matches only: once it reports
+        // one pattern's span, it resumes scanning past it, so a second
+        // pattern whose only occurrence overlaps that span is silently
+        // missed even though each pattern here is an independent question.
+        // `find_overlapping_iter` reports every match of every pattern.
+        for m in self.contains_ac.find_overlapping_iter(label) {
+            result[self.contains_ids[m.pattern().as_usize()]] = true;
+        }
+        for i in self.regex_set.matches(label).iter() {
+            result[self.regex_ids[i]] = true;
+        }
+        result
+    }
+}
+
+/// Translate a `*`/`?` QS pattern into an anchored regex: escape every regex
+/// metacharacter that can appear literally in a label, then expand the two
+/// glob wildcards into their regex equivalents.
+fn to_regex(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len() + 2);
+    out.push('^');
+    for c in pattern.chars() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '.' | '+' | '^' | '$' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('$');
+    out
+}
+
+/// The canonical pattern text a [`Pattern`] was built from, used to look its
+/// assigned id up in a [`CompiledLabelMatcher`].
+pub fn pattern_text(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::All => "*".to_string(),
+        Pattern::Contains(s) => format!("*{s}*"),
+        Pattern::Glob(g) => g.source().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompiledLabelMatcher;
+
+    #[test]
+    fn all_pattern_matches_every_label() {
+        let (matcher, ids) = CompiledLabelMatcher::compile(["*"].into_iter());
+        assert_eq!(matcher.matches("anything"), vec![true]);
+        assert_eq!(ids["*"], 0);
+    }
+
+    #[test]
+    fn contains_pattern_uses_aho_corasick_path() {
+        let (matcher, ids) = CompiledLabelMatcher::compile(["*A:-1+*"].into_iter());
+        assert!(matcher.matches("xx/A:-1+yy")[ids["*A:-1+*"]]);
+        assert!(!matcher.matches("xx/A:-2+yy")[ids["*A:-1+*"]]);
+    }
+
+    #[test]
+    fn glob_pattern_uses_regex_set_path() {
+        let (matcher, ids) = CompiledLabelMatcher::compile(["*/A:-??+*"].into_iter());
+        assert!(matcher.matches("xx/A:-12+yy")[ids["*/A:-??+*"]]);
+        assert!(!matcher.matches("xx/A:-1+yy")[ids["*/A:-??+*"]]);
+    }
+
+    #[test]
+    fn distinct_patterns_get_distinct_ids_and_are_evaluated_independently() {
+        let (matcher, ids) = CompiledLabelMatcher::compile(["*", "*foo*", "a?c"].into_iter());
+        let bits = matcher.matches("xfooc");
+        assert!(bits[ids["*"]]);
+        assert!(bits[ids["*foo*"]]);
+        assert!(!bits[ids["a?c"]]);
+
+        let bits = matcher.matches("abc");
+        assert!(bits[ids["*"]]);
+        assert!(!bits[ids["*foo*"]]);
+        assert!(bits[ids["a?c"]]);
+    }
+
+    #[test]
+    fn duplicate_pattern_strings_share_one_id() {
+        let (_, ids) = CompiledLabelMatcher::compile(["*foo*", "*foo*", "*"].into_iter());
+        assert_eq!(ids.len(), 2);
+    }
+
+    #[test]
+    fn literal_dot_in_a_glob_pattern_is_not_treated_as_a_wildcard() {
+        let (matcher, ids) = CompiledLabelMatcher::compile(["a.c"].into_iter());
+        assert!(matcher.matches("a.c")[ids["a.c"]]);
+        assert!(!matcher.matches("abc")[ids["a.c"]]);
+    }
+
+    #[test]
+    fn overlapping_contains_patterns_are_both_matched() {
+        // "ab" ([0,2)) and "ba" ([1,3)) overlap in "aba"; a non-overlapping
+        // scan would report only the first and skip the second.
+        let (matcher, ids) = CompiledLabelMatcher::compile(["*ab*", "*ba*"].into_iter());
+        let bits = matcher.matches("aba");
+        assert!(bits[ids["*ab*"]]);
+        assert!(bits[ids["*ba*"]]);
+    }
+}
@@ -0,0 +1,129 @@
+//! A compiled `*`/`?` glob matcher for decision-tree `QS` question patterns
+//! that aren't the trivial `*` or `*substr*` shapes handled directly by
+//! [`super::compiled_matcher::CompiledLabelMatcher`] (`PatternKind::All` and
+//! `PatternKind::Contains`).
+//!
+//! [`super::compiled_matcher::CompiledLabelMatcher`] evaluates these
+//! patterns itself via a `RegexSet` so a whole model's worth of patterns can
+//! be matched against a label in one pass; `GlobMatcher` isn't on that hot
+//! path. It's what [`super::stream::Pattern::Glob`] actually stores: the
+//! value compared for equality, round-tripped through [`Self::source`] into
+//! the binary cache format, and recompiled from it without re-parsing the
+//! whole tree.
+
+/// A single token of a compiled pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GlobToken {
+    Literal(char),
+    Any,
+    Star,
+}
+
+/// A pattern compiled once at parse time and matched repeatedly afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobMatcher {
+    source: String,
+    tokens: Vec<GlobToken>,
+}
+
+impl GlobMatcher {
+    pub fn compile(pattern: &str) -> Self {
+        let tokens = pattern
+            .chars()
+            .map(|c| match c {
+                '*' => GlobToken::Star,
+                '?' => GlobToken::Any,
+                c => GlobToken::Literal(c),
+            })
+            .collect();
+        Self {
+            source: pattern.to_string(),
+            tokens,
+        }
+    }
+
+    /// The original `*`/`?` pattern string this matcher was compiled from,
+    /// kept around so a [`super::stream::Pattern::Glob`] can be serialized
+    /// into a [`crate::model::cache`] entry and recompiled verbatim.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Classic two-pointer glob match: walk the text and pattern together;
+    /// on a literal/`?` advance both, on `*` remember the star position and
+    /// advance only the pattern, and on a mismatch backtrack to the last
+    /// star while advancing the remembered text position. Succeeds only
+    /// when the remaining pattern is all `*`.
+    pub fn is_match(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+
+        let (mut ti, mut pi) = (0usize, 0usize);
+        // (pattern position just after the star, text position the star last consumed up to)
+        let mut star: Option<(usize, usize)> = None;
+
+        while ti < text.len() {
+            match self.tokens.get(pi) {
+                Some(GlobToken::Any) => {
+                    ti += 1;
+                    pi += 1;
+                }
+                Some(GlobToken::Literal(c)) if *c == text[ti] => {
+                    ti += 1;
+                    pi += 1;
+                }
+                Some(GlobToken::Star) => {
+                    star = Some((pi + 1, ti));
+                    pi += 1;
+                }
+                _ => match star {
+                    Some((sp, st)) => {
+                        pi = sp;
+                        ti = st + 1;
+                        star = Some((sp, ti));
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        self.tokens[pi..]
+            .iter()
+            .all(|t| matches!(t, GlobToken::Star))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlobMatcher;
+
+    #[test]
+    fn compile_round_trips_the_source_text() {
+        assert_eq!(GlobMatcher::compile("*/A:-??+*").source(), "*/A:-??+*");
+        assert_eq!(GlobMatcher::compile("abc").source(), "abc");
+    }
+
+    #[test]
+    fn literal() {
+        assert!(GlobMatcher::compile("abc").is_match("abc"));
+        assert!(!GlobMatcher::compile("abc").is_match("abd"));
+    }
+
+    #[test]
+    fn star_both_ends() {
+        let m = GlobMatcher::compile("*/A:-??+*");
+        assert!(m.is_match("xx/A:-12+yy"));
+        assert!(!m.is_match("xx/A:-1+yy"));
+    }
+
+    #[test]
+    fn any_matches_single_char() {
+        assert!(GlobMatcher::compile("a?c").is_match("abc"));
+        assert!(!GlobMatcher::compile("a?c").is_match("abbc"));
+    }
+
+    #[test]
+    fn all_wildcard() {
+        assert!(GlobMatcher::compile("*").is_match(""));
+        assert!(GlobMatcher::compile("*").is_match("anything"));
+    }
+}
@@ -0,0 +1,340 @@
+//! Source-located parsing diagnostics for htsvoice/tree files.
+//!
+//! `nom::error::VerboseError` carries no notion of *where* in the original
+//! file a failure happened, so a malformed `.htsvoice` only ever produces an
+//! opaque nom trace. [`Traced`] wraps a `&str` input while carrying the byte
+//! offset from the start of the file, and [`SourceError`] records that offset
+//! together with the expected token/context label and the offending slice so
+//! [`SourceError::render`] can print a human-readable report with a `^` caret
+//! under the bad span.
+
+use std::ops::{Range, RangeFrom, RangeTo};
+
+use nom::{
+    error::{ContextError, ErrorKind, ParseError},
+    Compare, CompareResult, IResult, InputIter, InputLength, InputTake, InputTakeAtPosition,
+    Needed, Offset, ParseTo, Slice,
+};
+
+use super::base::ParseTarget;
+
+/// A `&str` fragment paired with the byte offset of its start within the
+/// original source. Slicing/taking preserves the running offset so an error
+/// produced deep inside a parser chain can still be traced back to its
+/// position in the original file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Traced<'a> {
+    pub fragment: &'a str,
+    pub offset: usize,
+}
+
+impl<'a> Traced<'a> {
+    pub fn new(fragment: &'a str) -> Self {
+        Self { fragment, offset: 0 }
+    }
+}
+
+impl<'a> Slice<Range<usize>> for Traced<'a> {
+    fn slice(&self, range: Range<usize>) -> Self {
+        Traced {
+            fragment: self.fragment.slice(range.clone()),
+            offset: self.offset + range.start,
+        }
+    }
+}
+impl<'a> Slice<RangeFrom<usize>> for Traced<'a> {
+    fn slice(&self, range: RangeFrom<usize>) -> Self {
+        Traced {
+            fragment: self.fragment.slice(range.clone()),
+            offset: self.offset + range.start,
+        }
+    }
+}
+impl<'a> Slice<RangeTo<usize>> for Traced<'a> {
+    fn slice(&self, range: RangeTo<usize>) -> Self {
+        Traced {
+            fragment: self.fragment.slice(range),
+            offset: self.offset,
+        }
+    }
+}
+
+impl<'a> InputIter for Traced<'a> {
+    type Item = char;
+    type Iter = std::str::CharIndices<'a>;
+    type IterElem = std::str::Chars<'a>;
+    fn iter_indices(&self) -> Self::Iter {
+        self.fragment.iter_indices()
+    }
+    fn iter_elements(&self) -> Self::IterElem {
+        self.fragment.iter_elements()
+    }
+    fn position<P: Fn(Self::Item) -> bool>(&self, predicate: P) -> Option<usize> {
+        self.fragment.position(predicate)
+    }
+    fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        self.fragment.slice_index(count)
+    }
+}
+
+impl<'a> InputLength for Traced<'a> {
+    fn input_len(&self) -> usize {
+        self.fragment.input_len()
+    }
+}
+
+impl<'a> InputTake for Traced<'a> {
+    fn take(&self, count: usize) -> Self {
+        self.slice(..count)
+    }
+    fn take_split(&self, count: usize) -> (Self, Self) {
+        (self.slice(count..), self.slice(..count))
+    }
+}
+
+impl<'a> InputTakeAtPosition for Traced<'a> {
+    type Item = char;
+
+    fn split_at_position<P, E: ParseError<Self>>(&self, predicate: P) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.fragment.find(predicate) {
+            Some(i) => Ok((self.slice(i..), self.slice(..i))),
+            None => Err(nom::Err::Incomplete(Needed::Unknown)),
+        }
+    }
+
+    fn split_at_position1<P, E: ParseError<Self>>(
+        &self,
+        predicate: P,
+        kind: ErrorKind,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.fragment.find(predicate) {
+            Some(0) => Err(nom::Err::Error(E::from_error_kind(*self, kind))),
+            Some(i) => Ok((self.slice(i..), self.slice(..i))),
+            None => Err(nom::Err::Incomplete(Needed::Unknown)),
+        }
+    }
+
+    fn split_at_position_complete<P, E: ParseError<Self>>(
+        &self,
+        predicate: P,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.split_at_position(predicate) {
+            Err(nom::Err::Incomplete(_)) => Ok((self.slice(self.fragment.len()..), *self)),
+            res => res,
+        }
+    }
+
+    fn split_at_position1_complete<P, E: ParseError<Self>>(
+        &self,
+        predicate: P,
+        kind: ErrorKind,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        match self.fragment.find(predicate) {
+            Some(0) => Err(nom::Err::Error(E::from_error_kind(*self, kind))),
+            Some(i) => Ok((self.slice(i..), self.slice(..i))),
+            None if self.fragment.is_empty() => {
+                Err(nom::Err::Error(E::from_error_kind(*self, kind)))
+            }
+            None => Ok((self.slice(self.fragment.len()..), *self)),
+        }
+    }
+}
+
+impl<'a> Offset for Traced<'a> {
+    fn offset(&self, second: &Self) -> usize {
+        second.offset - self.offset
+    }
+}
+
+impl<'a> ParseTo<isize> for Traced<'a> {
+    fn parse_to(&self) -> Option<isize> {
+        self.fragment.parse().ok()
+    }
+}
+
+impl<'a> Compare<&'static str> for Traced<'a> {
+    fn compare(&self, t: &'static str) -> CompareResult {
+        self.fragment.compare(t)
+    }
+    fn compare_no_case(&self, t: &'static str) -> CompareResult {
+        self.fragment.compare_no_case(t)
+    }
+}
+
+impl<'a> ParseTarget for Traced<'a> {
+    fn parse_template<F, E>(self, cond: F) -> IResult<Self, Self, E>
+    where
+        F: Fn(char) -> bool,
+        E: ParseError<Self>,
+    {
+        let (rest, fragment) = <&str as ParseTarget>::parse_template::<F, ()>(self.fragment, cond)
+            .map_err(|_: nom::Err<()>| {
+                nom::Err::Error(E::from_error_kind(self, ErrorKind::TakeWhile1))
+            })?;
+        let taken = self.fragment.len() - rest.len();
+        Ok((self.slice(taken..), self.slice(..taken)))
+    }
+    fn parse_template1<F, E>(self, cond: F) -> IResult<Self, Self, E>
+    where
+        F: Fn(char) -> bool,
+        E: ParseError<Self>,
+    {
+        let (rest, fragment) =
+            <&str as ParseTarget>::parse_template1::<F, ()>(self.fragment, cond)
+                .map_err(|_: nom::Err<()>| {
+                    nom::Err::Error(E::from_error_kind(self, ErrorKind::TakeWhile1))
+                })?;
+        let _ = fragment;
+        let taken = self.fragment.len() - rest.len();
+        Ok((self.slice(taken..), self.slice(..taken)))
+    }
+
+    fn parse_ascii_to_string<E: ParseError<Self>>(&self) -> IResult<Self, String, E> {
+        Self::parse_ascii(*self).map(|(rest, result)| (rest, result.fragment.to_string()))
+    }
+}
+
+/// A parse error located at a byte offset in the original source, together
+/// with the label of what was expected there and the slice that was actually
+/// found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError {
+    pub offset: usize,
+    pub context: Vec<&'static str>,
+    pub found: String,
+}
+
+impl<'a> ParseError<Traced<'a>> for SourceError {
+    fn from_error_kind(input: Traced<'a>, _kind: ErrorKind) -> Self {
+        Self {
+            offset: input.offset,
+            context: Vec::new(),
+            found: first_line(input.fragment),
+        }
+    }
+    fn append(_input: Traced<'a>, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> ContextError<Traced<'a>> for SourceError {
+    fn add_context(input: Traced<'a>, ctx: &'static str, mut other: Self) -> Self {
+        other.offset = input.offset;
+        other.context.push(ctx);
+        other
+    }
+}
+
+fn first_line(s: &str) -> String {
+    s.lines().next().unwrap_or("").to_string()
+}
+
+/// The display width of `prefix` for caret alignment: a tab advances to the
+/// next multiple of 8 columns (so its width depends on the accumulated
+/// column position, not just the character itself), wide (East-Asian)
+/// characters occupy two columns, and everything else occupies one.
+fn display_width(prefix: &str) -> usize {
+    prefix.chars().fold(0usize, |width, c| {
+        if c == '\t' {
+            width + (8 - width % 8)
+        } else {
+            let cp = c as u32;
+            let wide = (0x1100..=0x115F).contains(&cp)
+                || (0x2E80..=0xA4CF).contains(&cp)
+                || (0xAC00..=0xD7A3).contains(&cp)
+                || (0xF900..=0xFAFF).contains(&cp)
+                || (0xFF00..=0xFF60).contains(&cp)
+                || (0xFFE0..=0xFFE6).contains(&cp)
+                || (0x20000..=0x3FFFD).contains(&cp);
+            width + if wide { 2 } else { 1 }
+        }
+    })
+}
+
+impl SourceError {
+    /// Render a human-readable report: the 1-based `line:column`, the
+    /// offending source line, and a `^` caret under the bad span.
+    pub fn render(&self, source: &str, file_name: &str) -> String {
+        let mut line_no = 1usize;
+        let mut line_start = 0usize;
+        for (i, c) in source.char_indices() {
+            if i >= self.offset {
+                break;
+            }
+            if c == '\n' {
+                line_no += 1;
+                line_start = i + 1;
+            }
+        }
+        let line = source[line_start..]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim_end_matches('\r');
+        // Expand tabs with the same stops `display_width` uses, so the
+        // printed line and the caret padding agree behind the gutter.
+        let mut src = String::new();
+        for c in line.chars() {
+            if c == '\t' {
+                let width = display_width(&src);
+                src.push_str(&" ".repeat(8 - width % 8));
+            } else {
+                src.push(c);
+            }
+        }
+
+        let column_chars = source[line_start..self.offset].chars().count() + 1;
+        let caret_offset: usize = display_width(&source[line_start..self.offset]);
+
+        let expected = self
+            .context
+            .last()
+            .copied()
+            .unwrap_or("valid input");
+
+        format!(
+            "{file}:{line_no}:{column_chars}: error: expected {expected}, found `{found}`\n  {src}\n  {caret:>width$}",
+            file = file_name,
+            line_no = line_no,
+            column_chars = column_chars,
+            expected = expected,
+            found = self.found,
+            src = src,
+            caret = "^",
+            width = caret_offset + 1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceError;
+
+    #[test]
+    fn render_aligns_caret_past_a_leading_tab() {
+        // "\tbogus" has a tab occupying columns 1-8, so the caret under the
+        // 'b' (byte offset 1) must land on column 9, not column 2.
+        let source = "\tbogus";
+        let err = SourceError {
+            offset: 1,
+            context: Vec::new(),
+            found: "bogus".to_string(),
+        };
+        let rendered = err.render(source, "test");
+        let caret_line = rendered.lines().nth(2).unwrap();
+        // 2-space gutter + 8 columns of tab stop = column 10 (0-based).
+        assert_eq!(caret_line.find('^'), Some(10));
+    }
+}
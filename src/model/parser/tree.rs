@@ -12,6 +12,7 @@ use nom::{
 };
 
 use super::base::ParseTarget;
+use super::diagnostics::{SourceError, Traced};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tree {
@@ -178,11 +179,40 @@ where
     }
 }
 
+/// Parse a single `{...}[N]{...}` HTS tree definition, reporting a located,
+/// captioned error instead of an opaque nom trace when `source` is
+/// malformed.
+///
+/// This is the only entry point wired up to `Traced`/`SourceError` in this
+/// checkout. The request also asks for the model/pdf parsers to report
+/// located errors the same way, but there is no such parser here to wire:
+/// `base.rs`'s `ParseTarget` is already generic over `Traced` (every method
+/// on it works for `Traced` for free, same as it does here for `&str`), and
+/// `convert.rs` takes an already-parsed [`Tree`] and a question lookup table
+/// as plain Rust values — there's no source text left by the time it runs,
+/// so there's nothing for a `SourceError` to point a caret at. A parser that
+/// reads `.pdf`/model-header bytes into a `Tree`/`ModelParameter` and could
+/// surface a location the way this one does isn't part of this checkout.
+pub fn parse(
+    source: &str,
+    file_name: &str,
+) -> Result<Tree, crate::model::stream::ModelError> {
+    match TreeParser::<Traced>::parse_tree::<SourceError>(Traced::new(source)) {
+        Ok((_, tree)) => Ok(tree),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(
+            crate::model::stream::ModelError::Parse(e.render(source, file_name)),
+        ),
+        Err(nom::Err::Incomplete(_)) => Err(crate::model::stream::ModelError::Parse(format!(
+            "{file_name}: unexpected end of input"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nom::error::VerboseError;
 
-    use super::{Node, Tree, TreeIndex, TreeParser};
+    use super::{parse, Node, Tree, TreeIndex, TreeParser};
 
     #[test]
     fn parse_question() {
@@ -252,4 +282,23 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn parse_located_reports_offset_of_failure() {
+        let source = "{*}[2]\n{\n    0 Utt_Len_Mora<=28   bogus_no_quotes   -1\n}";
+        let err = parse(source, "voice.htsvoice").unwrap_err();
+        let rendered = err.to_string();
+        assert!(rendered.starts_with("voice.htsvoice:3:"), "{rendered}");
+        assert!(rendered.contains('^'), "{rendered}");
+    }
+
+    #[test]
+    fn parse_located_accepts_well_formed_tree() {
+        let source = r#"{*}[2]
+{
+    0 Utt_Len_Mora<=28                                    "gv_lf0_1"          -1
+    -1 Utt_Len_Mora=18                                     "gv_lf0_3"       "gv_lf0_2"
+}"#;
+        assert!(parse(source, "voice.htsvoice").is_ok());
+    }
 }
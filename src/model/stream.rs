@@ -1,7 +1,9 @@
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::sync::Mutex;
 
-use regex::Regex;
-
+use super::compiled_matcher::{pattern_text, CompiledLabelMatcher};
+use super::glob::GlobMatcher;
 use super::window::Windows;
 
 pub struct StreamModels {
@@ -47,6 +49,70 @@ impl StreamModels {
             windows,
         }
     }
+
+    /// Interpolate the parameter at `(state_index, label)` across several
+    /// voices' stream models, implementing classic HTS speaker/style
+    /// interpolation: starting from a zeroed [`ModelParameter`], each
+    /// voice's parameter is folded in with `add_assign(weight_i, param_i)`.
+    /// `weights` should sum to 1 and must have one entry per voice in
+    /// `voices`.
+    ///
+    /// Not yet called from [`crate::engine::Engine`]: its multi-voice mixing
+    /// goes through `Models`/`InterporationWeight`, neither of which exists
+    /// in this checkout (see `Engine::build_stream`), so there's nothing to
+    /// wire this into without guessing at that plumbing's internals. This
+    /// is the primitive such wiring would call.
+    pub fn get_interpolated_parameter(
+        voices: &[&StreamModels],
+        weights: &[f64],
+        state_index: usize,
+        label: &str,
+    ) -> Result<ModelParameter, InterpolationError> {
+        if weights.len() != voices.len() {
+            return Err(InterpolationError::WeightCountMismatch {
+                weights: weights.len(),
+                voices: voices.len(),
+            });
+        }
+        let Some((first, rest)) = voices.split_first() else {
+            return Err(InterpolationError::NoVoices);
+        };
+        if rest
+            .iter()
+            .any(|v| !v.metadata.interpolation_compatible(&first.metadata))
+        {
+            return Err(InterpolationError::MismatchedMetadata);
+        }
+
+        let mut params = voices
+            .iter()
+            .map(|v| v.stream_model.get_parameter(state_index, label));
+        let first_param = params.next().expect("voices is non-empty")?;
+
+        let mut result =
+            ModelParameter::new(first_param.parameters.len(), first_param.msd.is_some());
+        result.add_assign(weights[0], first_param);
+        for (param, &weight) in params.zip(&weights[1..]) {
+            result.add_assign(weight, param?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Errors from [`StreamModels::get_interpolated_parameter`].
+#[derive(Debug, thiserror::Error)]
+pub enum InterpolationError {
+    #[error("got {weights} weights for {voices} voices")]
+    WeightCountMismatch { weights: usize, voices: usize },
+    #[error("no voices to interpolate between")]
+    NoVoices,
+    #[error(
+        "voices have inconsistent stream metadata; interpolation requires matching vector_length, num_windows and is_msd"
+    )]
+    MismatchedMetadata,
+    #[error(transparent)]
+    Model(#[from] ModelError),
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -58,9 +124,35 @@ pub struct StreamModelMetadata {
     pub option: Vec<String>,
 }
 
+impl StreamModelMetadata {
+    /// Whether `self` and `other` agree on the fields
+    /// [`StreamModels::get_interpolated_parameter`] actually relies on
+    /// (`vector_length`, `num_windows`, `is_msd`). `use_gv` and `option` may
+    /// differ between otherwise-interpolatable voices.
+    fn interpolation_compatible(&self, other: &Self) -> bool {
+        self.vector_length == other.vector_length
+            && self.num_windows == other.num_windows
+            && self.is_msd == other.is_msd
+    }
+}
+
 pub struct Model {
     trees: Vec<Tree>,
     pdf: Vec<Vec<ModelParameter>>,
+    /// Memoizes `(state_index, label) -> (tree_index, pdf_index)` so that
+    /// repeated frames sharing a context label skip tree traversal entirely.
+    /// A `Mutex` rather than a `RefCell` so `Model` stays `Sync`, since it's
+    /// shared by reference across the `parallel` feature's rayon threads.
+    index_cache: Mutex<HashMap<(usize, String), (Option<usize>, Option<usize>)>>,
+    /// Single-pass Aho-Corasick/RegexSet matcher over every distinct pattern
+    /// used by `trees`, so a label is evaluated against every pattern once
+    /// instead of once per node visited during traversal.
+    compiled: CompiledLabelMatcher,
+    /// `trees[i].patterns` translated into ids into `compiled`.
+    tree_pattern_ids: Vec<Vec<usize>>,
+    /// `trees[i].nodes[j]`'s patterns translated into ids into `compiled`;
+    /// `None` for `TreeNode::Leaf`.
+    node_pattern_ids: Vec<Vec<Option<Vec<usize>>>>,
 }
 
 impl Display for Model {
@@ -95,45 +187,212 @@ impl Display for Model {
 
 impl Model {
     pub fn new(trees: Vec<Tree>, pdf: Vec<Vec<ModelParameter>>) -> Self {
-        Self { trees, pdf }
+        let tree_patterns = trees.iter().flat_map(|t| t.patterns.iter().map(pattern_text));
+        let node_patterns = trees.iter().flat_map(|t| {
+            t.nodes.iter().flat_map(|n| match n {
+                TreeNode::Node { patterns, .. } => {
+                    patterns.iter().map(pattern_text).collect::<Vec<_>>()
+                }
+                TreeNode::Leaf { .. } => Vec::new(),
+            })
+        });
+        let all_patterns: Vec<String> = tree_patterns.chain(node_patterns).collect();
+        let (compiled, ids) =
+            CompiledLabelMatcher::compile(all_patterns.iter().map(String::as_str));
+
+        let tree_pattern_ids = trees
+            .iter()
+            .map(|t| t.patterns.iter().map(|p| ids[&pattern_text(p)]).collect())
+            .collect();
+        let node_pattern_ids = trees
+            .iter()
+            .map(|t| {
+                t.nodes
+                    .iter()
+                    .map(|n| match n {
+                        TreeNode::Node { patterns, .. } => {
+                            Some(patterns.iter().map(|p| ids[&pattern_text(p)]).collect())
+                        }
+                        TreeNode::Leaf { .. } => None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            trees,
+            pdf,
+            index_cache: Mutex::new(HashMap::new()),
+            compiled,
+            tree_pattern_ids,
+            node_pattern_ids,
+        }
     }
 
     /// Get index of tree and PDF
     /// Returns (tree_index, pdf_index)
     pub fn get_index(&self, state_index: usize, string: &str) -> (Option<usize>, Option<usize>) {
-        let tree_index = self.find_tree_index(state_index, string);
+        if let Some(cached) = self
+            .index_cache
+            .lock()
+            .unwrap()
+            .get(&(state_index, string.to_string()))
+        {
+            return *cached;
+        }
 
-        let tree = match tree_index {
-            Some(idx) => &self.trees[idx],
-            None => &self.trees[0],
-        };
+        // Evaluate every distinct pattern against the label once, rather
+        // than once per tree/node visited during traversal below.
+        let bits = self.compiled.matches(string);
 
-        let pdf_index = tree.search_node(string);
+        let tree_index = self.find_tree_index(state_index, &bits);
+        let pdf_index = self.search_node(tree_index.unwrap_or(0), &bits);
 
-        (
+        let result = (
             tree_index
                 // Somehow hts_engine_API requires 2 to be added to tree index
                 .map(|index| index + 2),
             pdf_index,
-        )
+        );
+
+        self.index_cache
+            .lock()
+            .unwrap()
+            .insert((state_index, string.to_string()), result);
+
+        result
     }
-    fn find_tree_index(&self, state_index: usize, string: &str) -> Option<usize> {
-        self.trees
-            .iter()
-            .enumerate()
-            .position(|(_, tree)| tree.state == state_index && tree.matches_pattern(string))
+    /// The decision trees backing this model, one per state. Exposed for
+    /// [`crate::model::cache`] serialization.
+    pub fn trees(&self) -> &[Tree] {
+        &self.trees
+    }
+    /// The PDFs backing this model, indexed by `[tree_index - 2][pdf_index - 1]`.
+    /// Exposed for [`crate::model::cache`] serialization.
+    pub fn pdf(&self) -> &[Vec<ModelParameter>] {
+        &self.pdf
+    }
+
+    fn find_tree_index(&self, state_index: usize, bits: &[bool]) -> Option<usize> {
+        self.trees.iter().enumerate().position(|(i, tree)| {
+            tree.state == state_index && self.tree_pattern_ids[i].iter().any(|&id| bits[id])
+        })
+    }
+
+    /// Tree search using the precompiled pattern bitset: each node visit is
+    /// a handful of `bits[id]` lookups instead of a pattern evaluation.
+    fn search_node(&self, tree_index: usize, bits: &[bool]) -> Option<usize> {
+        let tree = &self.trees[tree_index];
+        let mut node_index = 0;
+
+        while let Some(node) = tree.nodes.get(node_index) {
+            match node {
+                TreeNode::Leaf { pdf_index } => return Some(*pdf_index),
+                TreeNode::Node { yes, no, .. } => {
+                    let ids = self.node_pattern_ids[tree_index][node_index]
+                        .as_ref()
+                        .expect("TreeNode::Node always has pattern ids");
+                    node_index = if ids.iter().any(|&id| bits[id]) {
+                        *yes
+                    } else {
+                        *no
+                    };
+                }
+            }
+        }
+
+        None
     }
 
     /// Get parameter using interpolation weight
-    pub fn get_parameter(&self, state_index: usize, string: &str) -> &ModelParameter {
+    pub fn get_parameter(&self, state_index: usize, string: &str) -> Result<&ModelParameter, ModelError> {
         let (Some(tree_index), Some(pdf_index)) = self.get_index(state_index, string) else {
-            todo!("index not found!")
+            return Err(ModelError::IndexNotFound {
+                state_index,
+                label: string.to_string(),
+            });
         };
 
-        &self.pdf[tree_index - 2][pdf_index - 1]
+        Ok(&self.pdf[tree_index - 2][pdf_index - 1])
+    }
+
+    /// Walk the decision tree for `(state_index, label)`, recording every
+    /// branch taken along the way to the selected pdf leaf. Lets tooling
+    /// explain *why* a label resolved to a given pdf, rather than only
+    /// returning the final index like [`Self::get_index`].
+    pub fn trace_decision(
+        &self,
+        state_index: usize,
+        label: &str,
+    ) -> Result<DecisionTrace, ModelError> {
+        let err = || ModelError::IndexNotFound {
+            state_index,
+            label: label.to_string(),
+        };
+
+        let bits = self.compiled.matches(label);
+        let tree_index = self.find_tree_index(state_index, &bits).ok_or_else(err)?;
+
+        let tree = &self.trees[tree_index];
+        let mut node_index = 0;
+        let mut steps = Vec::new();
+
+        loop {
+            match tree.nodes.get(node_index) {
+                Some(TreeNode::Leaf { pdf_index }) => {
+                    return Ok(DecisionTrace {
+                        // Somehow hts_engine_API requires 2 to be added to tree index
+                        tree_index: tree_index + 2,
+                        steps,
+                        pdf_index: *pdf_index,
+                    });
+                }
+                Some(TreeNode::Node { patterns, yes, no }) => {
+                    let ids = self.node_pattern_ids[tree_index][node_index]
+                        .as_ref()
+                        .expect("TreeNode::Node always has pattern ids");
+                    let matched = ids.iter().any(|&id| bits[id]);
+                    steps.push(DecisionStep {
+                        patterns: patterns.clone(),
+                        matched,
+                    });
+                    node_index = if matched { *yes } else { *no };
+                }
+                None => return Err(err()),
+            }
+        }
     }
 }
 
+/// One branch taken while walking a decision tree toward its pdf leaf, as
+/// recorded by [`Model::trace_decision`].
+#[derive(Debug, Clone)]
+pub struct DecisionStep {
+    /// The question patterns evaluated at this node.
+    pub patterns: Vec<Pattern>,
+    /// Whether any of `patterns` matched the label, i.e. whether the `yes`
+    /// branch was taken.
+    pub matched: bool,
+}
+
+/// The full decision path from a model's root to the pdf it selected for a
+/// given `(state_index, label)` query, as recorded by [`Model::trace_decision`].
+#[derive(Debug, Clone)]
+pub struct DecisionTrace {
+    pub tree_index: usize,
+    pub steps: Vec<DecisionStep>,
+    pub pdf_index: usize,
+}
+
+/// Errors looking up a decision tree/pdf for a context label.
+#[derive(Debug, thiserror::Error)]
+pub enum ModelError {
+    #[error("no decision tree/pdf found for state {state_index} and label `{label}`")]
+    IndexNotFound { state_index: usize, label: String },
+    #[error("{0}")]
+    Parse(String),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ModelParameter {
     // (mean, vari)
@@ -179,33 +438,6 @@ pub struct Tree {
     pub nodes: Vec<TreeNode>,
 }
 
-impl Tree {
-    /// Pattern match
-    #[inline]
-    pub fn matches_pattern(&self, string: &str) -> bool {
-        self.patterns.iter().any(|p| p.is_match(string))
-    }
-    /// Tree search
-    pub fn search_node(&self, string: &str) -> Option<usize> {
-        let mut node_index = 0;
-
-        while let Some(node) = self.nodes.get(node_index) {
-            match node {
-                TreeNode::Leaf { pdf_index } => return Some(*pdf_index),
-                TreeNode::Node { patterns, yes, no } => {
-                    node_index = if patterns.iter().any(|p| p.is_match(string)) {
-                        *yes
-                    } else {
-                        *no
-                    }
-                }
-            }
-        }
-
-        None
-    }
-}
-
 #[derive(Debug, Clone)]
 pub enum TreeNode {
     Node {
@@ -222,11 +454,13 @@ pub enum TreeNode {
 pub enum Pattern {
     All,
     Contains(String),
-    Regex(Regex),
+    Glob(GlobMatcher),
 }
 
 impl Pattern {
-    pub fn from_pattern_string<T: AsRef<str>>(pattern: T) -> Result<Self, regex::Error> {
+    pub fn from_pattern_string<T: AsRef<str>>(
+        pattern: T,
+    ) -> Result<Self, std::convert::Infallible> {
         let pattern = pattern.as_ref();
         if pattern == "*" {
             Ok(Self::All)
@@ -236,22 +470,7 @@ impl Pattern {
         {
             Ok(Self::Contains(pattern[1..pattern.len() - 1].to_string()))
         } else {
-            Ok(Self::Regex(Regex::new(&format!(
-                "^{}$",
-                pattern
-                    .replace('+', "\\+")
-                    .replace('^', "\\^")
-                    .replace('|', "\\|")
-                    .replace('*', ".*")
-                    .replace('?', ".")
-            ))?))
-        }
-    }
-    pub fn is_match(&self, label: &str) -> bool {
-        match self {
-            Self::All => true,
-            Self::Contains(s) => label.contains(s),
-            Self::Regex(r) => r.is_match(label),
+            Ok(Self::Glob(GlobMatcher::compile(pattern)))
         }
     }
 }
@@ -261,7 +480,119 @@ impl PartialEq for Pattern {
         match self {
             Self::All => matches!(other, Self::All),
             Self::Contains(s1) => matches!(other,Self::Contains(s2) if s1==s2),
-            Self::Regex(r1) => matches!(other,Self::Regex(r2) if r1.as_str()==r2.as_str()),
+            Self::Glob(g1) => matches!(other, Self::Glob(g2) if g1 == g2),
         }
     }
 }
+
+// The metadata-mismatch and MSD-interpolation paths of
+// `StreamModels::get_interpolated_parameter` need an actual `StreamModels`
+// fixture, which needs a `Windows` value; `super::window` doesn't exist in
+// this checkout, so there's nothing to construct one from. The checks that
+// run before any `StreamModels` is touched (weight-count, no-voices) are
+// covered below.
+#[cfg(test)]
+mod tests {
+    use super::{
+        InterpolationError, Model, ModelError, ModelParameter, Pattern, StreamModelMetadata,
+        StreamModels, Tree, TreeNode,
+    };
+
+    #[test]
+    fn weight_count_mismatch_is_rejected_before_touching_any_voice() {
+        let voices: Vec<&StreamModels> = Vec::new();
+        let err =
+            StreamModels::get_interpolated_parameter(&voices, &[1.0], 0, "x").unwrap_err();
+        assert!(matches!(
+            err,
+            InterpolationError::WeightCountMismatch {
+                weights: 1,
+                voices: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn no_voices_is_rejected_once_weight_count_matches() {
+        let voices: Vec<&StreamModels> = Vec::new();
+        let err = StreamModels::get_interpolated_parameter(&voices, &[], 0, "x").unwrap_err();
+        assert!(matches!(err, InterpolationError::NoVoices));
+    }
+
+    #[test]
+    fn interpolation_compatible_ignores_use_gv_and_option() {
+        let base = StreamModelMetadata {
+            vector_length: 3,
+            num_windows: 2,
+            is_msd: true,
+            use_gv: true,
+            option: vec!["a".to_string()],
+        };
+        let differs_in_irrelevant_fields = StreamModelMetadata {
+            use_gv: false,
+            option: vec!["b".to_string()],
+            ..base.clone()
+        };
+        assert!(base.interpolation_compatible(&differs_in_irrelevant_fields));
+
+        let differs_in_vector_length = StreamModelMetadata {
+            vector_length: 4,
+            ..base.clone()
+        };
+        assert!(!base.interpolation_compatible(&differs_in_vector_length));
+    }
+
+    fn sample_tree_model() -> Model {
+        let tree = Tree {
+            state: 2,
+            patterns: vec![Pattern::All],
+            nodes: vec![
+                TreeNode::Node {
+                    patterns: vec![Pattern::Contains("X".to_string())],
+                    yes: 1,
+                    no: 2,
+                },
+                TreeNode::Leaf { pdf_index: 1 },
+                TreeNode::Leaf { pdf_index: 2 },
+            ],
+        };
+        let pdf = vec![vec![
+            ModelParameter::new(1, false),
+            ModelParameter::new(1, false),
+        ]];
+        Model::new(vec![tree], pdf)
+    }
+
+    #[test]
+    fn trace_decision_records_the_yes_branch_to_its_leaf() {
+        let model = sample_tree_model();
+        let trace = model.trace_decision(2, "xXy").unwrap();
+        assert_eq!(trace.steps.len(), 1);
+        assert!(trace.steps[0].matched);
+        assert_eq!(trace.tree_index, 2);
+        assert_eq!(trace.pdf_index, 1);
+    }
+
+    #[test]
+    fn trace_decision_records_the_no_branch_to_its_leaf() {
+        let model = sample_tree_model();
+        let trace = model.trace_decision(2, "xYy").unwrap();
+        assert_eq!(trace.steps.len(), 1);
+        assert!(!trace.steps[0].matched);
+        assert_eq!(trace.tree_index, 2);
+        assert_eq!(trace.pdf_index, 2);
+    }
+
+    #[test]
+    fn trace_decision_rejects_a_state_with_no_matching_tree() {
+        let model = sample_tree_model();
+        let err = model.trace_decision(99, "xXy").unwrap_err();
+        assert!(matches!(
+            err,
+            ModelError::IndexNotFound {
+                state_index: 99,
+                ..
+            }
+        ));
+    }
+}
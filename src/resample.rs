@@ -0,0 +1,103 @@
+//! Sample-rate conversion via a windowed-sinc resampler.
+//!
+//! [`Condition::set_output_sampling_frequency`](crate::engine::Condition::set_output_sampling_frequency)
+//! uses this to let callers request an output rate independent of the voice
+//! model's native sampling frequency.
+
+/// Number of zero crossings of the sinc kernel on each side of its center;
+/// larger values trade compute for a sharper transition band.
+const HALF_TAPS: f64 = 16.0;
+
+/// Kaiser window shape parameter.
+const KAISER_BETA: f64 = 8.6;
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind, used to build
+/// the Kaiser window.
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..=20 {
+        term *= half_x / k as f64;
+        sum += term * term;
+    }
+    sum
+}
+
+fn kaiser(n: f64, half_width: f64, beta: f64) -> f64 {
+    let ratio = n / half_width;
+    if ratio.abs() > 1.0 {
+        return 0.0;
+    }
+    bessel_i0(beta * (1.0 - ratio * ratio).sqrt()) / bessel_i0(beta)
+}
+
+/// Resample `samples` from `in_rate` Hz to `out_rate` Hz.
+///
+/// Builds a Kaiser-windowed sinc kernel with its cutoff at
+/// `min(in_rate, out_rate) / 2` (so downsampling doesn't alias) and
+/// convolves it with a fractional delay per output sample. Returns
+/// `samples` unchanged if the rates already match.
+pub fn resample(samples: &[f64], in_rate: usize, out_rate: usize) -> Vec<f64> {
+    if in_rate == out_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let cutoff = in_rate.min(out_rate) as f64 / (2.0 * in_rate as f64);
+    let half_width = HALF_TAPS / (2.0 * cutoff);
+    let ratio = out_rate as f64 / in_rate as f64;
+    let out_len = ((samples.len() as f64) * ratio).round() as usize;
+
+    (0..out_len)
+        .map(|i| {
+            let t = i as f64 / ratio;
+            let lo = (t - half_width).ceil().max(0.0) as usize;
+            let hi = (t + half_width).floor().min((samples.len() - 1) as f64) as usize;
+            (lo..=hi)
+                .map(|n| {
+                    let d = t - n as f64;
+                    let kernel = 2.0 * cutoff * sinc(2.0 * cutoff * d) * kaiser(d, half_width, KAISER_BETA);
+                    samples[n] * kernel
+                })
+                .sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_rates_are_a_no_op() {
+        let samples = vec![0.1, 0.2, -0.3, 0.4];
+        assert_eq!(resample(&samples, 16000, 16000), samples);
+    }
+
+    #[test]
+    fn upsampling_preserves_low_frequency_content() {
+        let in_rate = 8000;
+        let out_rate = 16000;
+        let freq = 200.0;
+        let original: Vec<f64> = (0..in_rate)
+            .map(|i| (2.0 * std::f64::consts::PI * freq * i as f64 / in_rate as f64).sin())
+            .collect();
+        let resampled = resample(&original, in_rate, out_rate);
+
+        assert_eq!(resampled.len(), out_rate);
+        for i in 100..out_rate - 100 {
+            let expected =
+                (2.0 * std::f64::consts::PI * freq * i as f64 / out_rate as f64).sin();
+            assert!((resampled[i] - expected).abs() < 0.05);
+        }
+    }
+}
@@ -4,28 +4,58 @@ type Parameter = Vec<Vec<f64>>;
 
 pub struct SpeechGenerator {
     fperiod: usize,
-    alpha: f64,
-    beta: f64,
-    volume: f64,
+    /// Seed for the vocoder's excitation PRNG, making `synthesize` output
+    /// reproducible for a given seed and set of input parameters.
+    seed: u32,
 }
 
 impl SpeechGenerator {
-    pub fn new(fperiod: usize, alpha: f64, beta: f64, volume: f64) -> Self {
+    /// Seeded with a fixed default; use [`Self::new_with_seed`] for
+    /// reproducible-but-distinct output (e.g. [`crate::engine::Condition`]
+    /// threads its own configurable seed through that constructor rather
+    /// than this one, the same way it leaves most other fields at their
+    /// `Default` value unless a caller opts in via a setter).
+    pub fn new(fperiod: usize) -> Self {
+        Self::new_with_seed(fperiod, 1)
+    }
+    /// As [`Self::new`], but with an explicit excitation PRNG seed. A zero
+    /// seed is clamped to 1, since an all-zero xorshift state never changes.
+    pub fn new_with_seed(fperiod: usize, seed: u32) -> Self {
         Self {
             fperiod,
-            alpha,
-            beta,
-            volume,
+            seed: seed.max(1),
         }
     }
     /// Generate speech
     pub fn synthesize(
         &self,
-        mut v: Vocoder,
+        v: Vocoder,
         spectrum: Parameter,
         lf0: Parameter,
         lpf: Option<Parameter>,
     ) -> Vec<f64> {
+        let fperiod = self.fperiod;
+        self.synthesize_stream(v, spectrum, lf0, lpf)
+            .fold(Vec::new(), |mut speech, frame| {
+                debug_assert_eq!(frame.len(), fperiod);
+                speech.extend(frame);
+                speech
+            })
+    }
+
+    /// Frame-incremental synthesis: returns an iterator that yields one
+    /// `fperiod`-length block per call instead of synthesizing the whole
+    /// utterance up front. The vocoder's filter state is carried across
+    /// frames inside the returned [`SpeechStream`], so concatenating every
+    /// yielded block is sample-identical to [`Self::synthesize`].
+    pub fn synthesize_stream(
+        &self,
+        mut v: Vocoder,
+        spectrum: Parameter,
+        lf0: Parameter,
+        lpf: Option<Parameter>,
+    ) -> SpeechStream {
+        v.set_seed(self.seed);
         // check
         if lf0.len() > 0 {
             if lf0[0].len() != 1 {
@@ -36,23 +66,84 @@ impl SpeechGenerator {
             }
         }
 
-        // create speech buffer
-        let total_frame = lf0.len();
-        let mut speech = vec![0.0; total_frame * self.fperiod];
-
-        // synthesize speech waveform
-        for i in 0..total_frame {
-            v.synthesize(
-                lf0[i][0],
-                &spectrum[i],
-                lpf.as_ref().map(|lpf| &lpf[i] as &[f64]).unwrap_or(&[]),
-                self.alpha,
-                self.beta,
-                self.volume,
-                &mut speech[i * self.fperiod..(i + 1) * self.fperiod],
-            );
+        SpeechStream {
+            v,
+            spectrum,
+            lf0,
+            lpf,
+            fperiod: self.fperiod,
+            frame: 0,
+        }
+    }
+}
+
+/// A frame-incremental synthesis stream produced by
+/// [`SpeechGenerator::synthesize_stream`]. Each call to `next` advances the
+/// underlying [`Vocoder`] by one frame and yields the `fperiod` samples it
+/// produced; dropping the stream early simply stops synthesis.
+pub struct SpeechStream {
+    v: Vocoder,
+    spectrum: Parameter,
+    lf0: Parameter,
+    lpf: Option<Parameter>,
+    fperiod: usize,
+    frame: usize,
+}
+
+impl Iterator for SpeechStream {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.frame;
+        if i >= self.lf0.len() {
+            return None;
         }
+        self.frame += 1;
+
+        let mut block = vec![0.0; self.fperiod];
+        self.v.synthesize(
+            self.lf0[i][0],
+            &self.spectrum[i],
+            self.lpf.as_ref().map(|lpf| &lpf[i] as &[f64]).unwrap_or(&[]),
+            &mut block,
+        );
+        Some(block)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.lf0.len() - self.frame;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vocoder::Vocoder;
+
+    fn make_vocoder(fperiod: usize) -> Vocoder {
+        Vocoder::new(4, 0, false, 16000, fperiod, 0.42, 0.0, 1.0)
+    }
+
+    #[test]
+    fn streamed_frames_concatenate_to_the_batch_output() {
+        let fperiod = 4;
+        let spectrum: Parameter = vec![vec![0.1, 0.2, -0.1, 0.05, 0.0]; 3];
+        let lf0: Parameter = vec![vec![5.0]; 3];
+
+        let generator = SpeechGenerator::new_with_seed(fperiod, 7);
+
+        let batch = generator.synthesize(
+            make_vocoder(fperiod),
+            spectrum.clone(),
+            lf0.clone(),
+            None,
+        );
+        let streamed: Vec<f64> = generator
+            .synthesize_stream(make_vocoder(fperiod), spectrum, lf0, None)
+            .flatten()
+            .collect();
 
-        speech
+        assert_eq!(batch, streamed);
     }
 }
@@ -0,0 +1,198 @@
+/// Minimal xorshift32 PRNG used to drive the vocoder's noise excitation.
+///
+/// This avoids pulling in a full-featured RNG crate just to generate white
+/// noise for unvoiced/mixed frames, and its determinism (same seed + same
+/// input parameters always yields bit-identical output) makes `synthesize`
+/// reproducible across runs.
+#[derive(Debug, Clone)]
+pub struct Xorshift32 {
+    state: u32,
+}
+
+impl Xorshift32 {
+    /// `seed` must never be zero, since an all-zero state never changes; a
+    /// zero seed is clamped to 1.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            state: if seed == 0 { 1 } else { seed },
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut s = self.state;
+        s ^= s << 13;
+        s ^= s >> 17;
+        s ^= s << 5;
+        self.state = s;
+        s
+    }
+
+    /// Uniform sample in `[-1.0, 1.0)`.
+    pub fn next_uniform(&mut self) -> f64 {
+        // Divide by `u32::MAX + 1` rather than `u32::MAX`, since `next_u32`
+        // does reach `u32::MAX` somewhere in its period; dividing by
+        // `u32::MAX` would make that draw map to exactly `1.0`, outside the
+        // documented half-open range.
+        (self.next_u32() as f64 / (u32::MAX as f64 + 1.0)) * 2.0 - 1.0
+    }
+
+    /// Standard normal sample via Box-Muller.
+    pub fn next_gaussian(&mut self) -> f64 {
+        let u1 = (self.next_u32() as f64 + 1.0) / (u32::MAX as f64 + 2.0);
+        let u2 = self.next_u32() as f64 / u32::MAX as f64;
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+/// Pulse/noise excitation source for unvoiced and mixed frames.
+///
+/// Voiced frames emit a pulse train spaced `pitch` samples apart; unvoiced
+/// and mixed frames add noise shaped by the per-frame low-pass filter
+/// coefficients (`lpf`), drawn from a seeded [`Xorshift32`] so the same
+/// seed and input parameters reproduce the same waveform.
+///
+/// There's no `excitation.c` (or equivalent) in this checkout, so this
+/// pulse/noise mixing was written from the description above rather than
+/// ported from a reference implementation; its fidelity to the original
+/// HTS vocoder's excitation source couldn't be checked against one. The
+/// tests below cover the behavior this doc comment promises — pulse
+/// spacing and FIR shaping of the noise path — rather than a specific
+/// reference waveform.
+#[derive(Debug, Clone)]
+pub struct Excitation {
+    rng: Xorshift32,
+    pitch: f64,
+    pitch_count: f64,
+    noise_buffer: Vec<f64>,
+}
+
+impl Excitation {
+    pub fn new(pitch: f64, nlpf: usize, seed: u32) -> Self {
+        Self {
+            rng: Xorshift32::new(seed),
+            pitch,
+            pitch_count: 0.0,
+            noise_buffer: vec![0.0; nlpf.max(1)],
+        }
+    }
+
+    pub fn start(&mut self, pitch: f64, _fperiod: usize) {
+        self.pitch = pitch;
+    }
+
+    /// Draw the next excitation sample, shaping the noise component with
+    /// `lpf` (the per-frame low-pass filter coefficients) when present.
+    pub fn get(&mut self, lpf: &[f64]) -> f64 {
+        let noise = self.rng.next_gaussian();
+        let shaped = if lpf.is_empty() {
+            noise
+        } else {
+            self.noise_buffer.rotate_right(1);
+            self.noise_buffer[0] = noise;
+            lpf.iter()
+                .zip(self.noise_buffer.iter())
+                .map(|(c, n)| c * n)
+                .sum()
+        };
+
+        if self.pitch == 0.0 {
+            return shaped;
+        }
+
+        self.pitch_count += 1.0;
+        if self.pitch_count >= self.pitch {
+            self.pitch_count -= self.pitch;
+            1.0 + shaped
+        } else {
+            shaped
+        }
+    }
+
+    pub fn end(&mut self, pitch: f64) {
+        self.pitch = pitch;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Excitation, Xorshift32};
+
+    #[test]
+    fn voiced_frame_adds_a_periodic_pulse_every_pitch_samples() {
+        let pitch = 4.0;
+        // Same seed for both, so they draw the identical noise sequence;
+        // subtracting them isolates the pulse train's contribution.
+        let mut pulsed = Excitation::new(pitch, 0, 99);
+        let mut unvoiced = Excitation::new(0.0, 0, 99);
+
+        for i in 0..12usize {
+            let with_pulse = pulsed.get(&[]);
+            let without_pulse = unvoiced.get(&[]);
+            let pulse_contribution = with_pulse - without_pulse;
+            if (i + 1) % 4 == 0 {
+                assert!(
+                    (pulse_contribution - 1.0).abs() < 1e-9,
+                    "frame {i}: expected a pulse, got contribution {pulse_contribution}"
+                );
+            } else {
+                assert!(
+                    pulse_contribution.abs() < 1e-9,
+                    "frame {i}: expected no pulse, got contribution {pulse_contribution}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn noise_is_shaped_by_lpf_rather_than_passed_through_raw() {
+        let seed = 7;
+        let mut raw = Excitation::new(0.0, 0, seed);
+        let n0 = raw.get(&[]);
+
+        // A 2-tap FIR that only passes through the *previous* noise sample:
+        // the first draw should see the still-zeroed buffer slot (not the
+        // current raw noise), and the second should see `n0` delayed by one.
+        let lpf = [0.0, 1.0];
+        let mut shaped = Excitation::new(0.0, lpf.len(), seed);
+        let s0 = shaped.get(&lpf);
+        let s1 = shaped.get(&lpf);
+
+        assert_eq!(s0, 0.0);
+        assert_eq!(s1, n0);
+    }
+
+    #[test]
+    fn zero_seed_is_clamped() {
+        let mut a = Xorshift32::new(0);
+        let mut b = Xorshift32::new(1);
+        assert_eq!(a.next_u32(), b.next_u32());
+    }
+
+    #[test]
+    fn same_seed_is_deterministic() {
+        let mut a = Xorshift32::new(42);
+        let mut b = Xorshift32::new(42);
+        for _ in 0..8 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+    }
+
+    #[test]
+    fn uniform_is_in_range() {
+        let mut rng = Xorshift32::new(12345);
+        for _ in 0..1000 {
+            let v = rng.next_uniform();
+            assert!((-1.0..1.0).contains(&v));
+        }
+    }
+
+    #[test]
+    fn uniform_excludes_one_at_the_u32_max_boundary() {
+        // `next_u32` does reach `u32::MAX` somewhere in its ~4.29e9-long
+        // period, so sampling 1000 draws (as `uniform_is_in_range` does)
+        // can't catch a boundary mistake there; check the scaling formula
+        // directly at the input it must not map to exactly `1.0`.
+        let scaled = (u32::MAX as f64 / (u32::MAX as f64 + 1.0)) * 2.0 - 1.0;
+        assert!(scaled < 1.0);
+    }
+}
@@ -32,6 +32,8 @@ pub struct Vocoder {
     beta: f64,
     volume: f64,
 
+    /// Seed for the excitation PRNG; never zero (see [`Self::set_seed`]).
+    seed: u32,
     excitation: Option<Excitation>,
 }
 
@@ -60,10 +62,19 @@ impl Vocoder {
             beta,
             volume,
 
+            seed: 1,
             excitation: None,
         }
     }
 
+    /// Seed the excitation PRNG so `synthesize` is reproducible across runs.
+    /// A zero seed is clamped to 1, since an all-zero xorshift state never
+    /// changes. Has no effect once the excitation source has been created by
+    /// the first call to [`Self::synthesize`].
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed.max(1);
+    }
+
     pub fn synthesize(&mut self, lf0: f64, spectrum: &[f64], lpf: &[f64], rawdata: &mut [f64]) {
         let p = if lf0 == NODATA {
             0.0
@@ -120,7 +131,7 @@ impl Vocoder {
 
                 let excitation = self
                     .excitation
-                    .get_or_insert_with(|| Excitation::new(p, lpf.len()));
+                    .get_or_insert_with(|| Excitation::new(p, lpf.len(), self.seed));
                 excitation.start(p, self.fperiod);
 
                 (0..self.fperiod).for_each(|i| {
@@ -160,7 +171,7 @@ impl Vocoder {
 
                 let excitation = self
                     .excitation
-                    .get_or_insert_with(|| Excitation::new(p, lpf.len()));
+                    .get_or_insert_with(|| Excitation::new(p, lpf.len(), self.seed));
                 excitation.start(p, self.fperiod);
 
                 (0..self.fperiod).for_each(|i| {